@@ -0,0 +1,281 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Creates a bounded multi-producer/single-consumer command queue, split
+/// into a cloneable `Sender` and a `Receiver`.
+///
+/// Intended for typed messages, such as an enum of parameter changes, sent
+/// from several control threads to the audio thread. `capacity` is rounded
+/// up to a power of two. Sending is lock-free (a contended send retries a
+/// compare-and-swap) and receiving is wait-free; neither allocates.
+pub fn channel<T: Send>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+    let capacity = capacity.next_power_of_two();
+    let shared = Arc::new(Shared {
+        slots: (0..capacity)
+            .map(|i| Slot {
+                sequence: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect(),
+        mask: capacity - 1,
+        enqueue: AtomicUsize::new(0),
+        dequeue: AtomicUsize::new(0),
+    });
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Sending end of a command queue; clone it to give each control thread its own.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Receiving end of a command queue.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Bounded queue after Dmitry Vyukov's design. Positions are free-running
+/// counters; because the capacity is a power of two they map to the same
+/// slot on every lap, even across integer wrap.
+///
+/// A slot's `sequence` equals the position that may write it next; after
+/// the write it becomes `position + 1`, marking it readable, and after the
+/// read `position + capacity`, freeing it for the next lap.
+struct Shared<T> {
+    slots: Box<[Slot<T>]>,
+    mask: usize,
+    enqueue: AtomicUsize,
+    dequeue: AtomicUsize,
+}
+
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// Safety: each slot's value is accessed only by the one sender that claimed
+// its position with a compare-and-swap, or by the receiver once the
+// release/acquire on `sequence` marks it written.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let mut position = *self.dequeue.get_mut();
+        loop {
+            let slot = &mut self.slots[position & self.mask];
+            if *slot.sequence.get_mut() != position.wrapping_add(1) {
+                break;
+            }
+            // Safety: the sequence marks this slot as written and not yet read.
+            unsafe { slot.value.get_mut().assume_init_drop() };
+            position = position.wrapping_add(1);
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Sender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Queues `value`, or hands it back if the queue is full.
+    pub fn try_send(&self, value: T) -> Result<(), T> {
+        let shared = &*self.shared;
+        let mut position = shared.enqueue.load(Ordering::Relaxed);
+        loop {
+            let slot = &shared.slots[position & shared.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let lag = sequence.wrapping_sub(position) as isize;
+            if lag == 0 {
+                match shared.enqueue.compare_exchange_weak(
+                    position,
+                    position.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // Safety: the successful compare-and-swap gives this
+                        // sender exclusive use of the slot until `sequence`
+                        // is published below.
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.sequence
+                            .store(position.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => position = current,
+                }
+            } else if lag < 0 {
+                // The slot still holds an unread command from the previous lap.
+                return Err(value);
+            } else {
+                // Another sender claimed this position; catch up and retry.
+                position = shared.enqueue.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Removes the oldest command, or returns `None` if none is ready.
+    ///
+    /// A command whose sender has claimed a slot but not finished writing it
+    /// is not ready yet, and later commands wait behind it.
+    pub fn try_recv(&mut self) -> Option<T> {
+        let shared = &*self.shared;
+        let position = shared.dequeue.load(Ordering::Relaxed);
+        let slot = &shared.slots[position & shared.mask];
+        if slot.sequence.load(Ordering::Acquire) != position.wrapping_add(1) {
+            return None;
+        }
+        // Safety: the sequence marks this slot as written, and only the
+        // single receiver reads it.
+        let value = unsafe { (*slot.value.get()).assume_init_read() };
+        slot.sequence
+            .store(position.wrapping_add(shared.capacity()), Ordering::Release);
+        shared
+            .dequeue
+            .store(position.wrapping_add(1), Ordering::Relaxed);
+        Some(value)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Command {
+        SetGain { bus: usize, gain: f32 },
+        Mute(usize),
+    }
+
+    /// Creates a queue whose positions start at `start` instead of 0.
+    fn with_start_position<T: Send>(capacity: usize, start: usize) -> (Sender<T>, Receiver<T>) {
+        let (sender, receiver) = channel(capacity);
+        let shared = &sender.shared;
+        for lap_offset in 0..shared.capacity() {
+            let position = start.wrapping_add(lap_offset);
+            shared.slots[position & shared.mask]
+                .sequence
+                .store(position, Ordering::Relaxed);
+        }
+        shared.enqueue.store(start, Ordering::Relaxed);
+        shared.dequeue.store(start, Ordering::Relaxed);
+        (sender, receiver)
+    }
+
+    #[test]
+    fn fifo_order_and_bounds() {
+        let (sender, mut receiver) = channel(3);
+        assert_eq!(sender.capacity(), 4);
+        assert_eq!(receiver.try_recv(), None);
+        for bus in 0..4 {
+            sender.try_send(Command::Mute(bus)).unwrap();
+        }
+        assert_eq!(sender.try_send(Command::Mute(4)), Err(Command::Mute(4)));
+        assert_eq!(receiver.try_recv(), Some(Command::Mute(0)));
+        sender
+            .try_send(Command::SetGain { bus: 1, gain: 0.5 })
+            .unwrap();
+        for bus in 1..4 {
+            assert_eq!(receiver.try_recv(), Some(Command::Mute(bus)));
+        }
+        assert_eq!(
+            receiver.try_recv(),
+            Some(Command::SetGain { bus: 1, gain: 0.5 })
+        );
+        assert_eq!(receiver.try_recv(), None);
+    }
+
+    #[test]
+    fn positions_wrap_around_usize() {
+        let (sender, mut receiver) = with_start_position::<String>(4, usize::MAX - 5);
+        for round in 0..10 {
+            let sent: Vec<String> = (0..3).map(|i| format!("{round}-{i}")).collect();
+            for command in &sent {
+                sender.try_send(command.clone()).unwrap();
+            }
+            let received: Vec<String> = (0..3).map(|_| receiver.try_recv().unwrap()).collect();
+            assert_eq!(received, sent);
+        }
+        assert_eq!(receiver.try_recv(), None);
+    }
+
+    #[test]
+    fn multiple_senders_each_arrive_in_order() {
+        const PER_SENDER: usize = 20_000;
+        let (sender, mut receiver) = channel::<Command>(64);
+        let senders: Vec<_> = (0..4)
+            .map(|bus| {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_SENDER {
+                        let mut command = Command::SetGain {
+                            bus,
+                            gain: i as f32,
+                        };
+                        while let Err(rejected) = sender.try_send(command) {
+                            command = rejected;
+                            thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut next = [0.0f32; 4];
+        let mut received = 0;
+        while received < 4 * PER_SENDER {
+            match receiver.try_recv() {
+                Some(Command::SetGain { bus, gain }) => {
+                    assert_eq!(gain, next[bus]);
+                    next[bus] += 1.0;
+                    received += 1;
+                }
+                Some(other) => panic!("unexpected command {other:?}"),
+                None => thread::yield_now(),
+            }
+        }
+        senders
+            .into_iter()
+            .for_each(|handle| handle.join().unwrap());
+    }
+
+    #[test]
+    fn unread_commands_are_dropped() {
+        let item = Arc::new(());
+        let (sender, receiver) = channel(4);
+        sender.try_send(Arc::clone(&item)).unwrap();
+        sender.try_send(Arc::clone(&item)).unwrap();
+        drop(sender);
+        drop(receiver);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+}
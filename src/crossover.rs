@@ -0,0 +1,255 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use crate::denormal::DenormalGuard;
+
+/// Linkwitz-Riley 4th-order crossover splitting a mono signal into 2, 3 or
+/// 4 bands.
+///
+/// Bands are split from the bottom up: the input is divided at the lowest
+/// frequency, the high part at the next one, and so on. Each lower band is
+/// passed through the allpass response of every split above it, so all
+/// bands stay phase-aligned and summing them gives a flat magnitude response.
+pub struct Crossover {
+    splits: Vec<Split>,
+    /// Allpass compensation per band, for the splits above that band.
+    compensation: Vec<Vec<Biquad>>,
+}
+
+impl Crossover {
+    /// `frequencies` are the 1 to 3 crossover points in Hz, in ascending
+    /// order and below Nyquist.
+    pub fn new(sample_rate: f32, frequencies: &[f32]) -> Self {
+        assert!(
+            (1..=3).contains(&frequencies.len()),
+            "crossover supports 2 to 4 bands (1 to 3 frequencies)"
+        );
+        assert!(
+            frequencies.windows(2).all(|pair| pair[0] < pair[1]),
+            "crossover frequencies must be strictly ascending"
+        );
+        assert!(
+            frequencies
+                .iter()
+                .all(|&f| 0.0 < f && f < sample_rate / 2.0),
+            "crossover frequencies must lie between 0 and Nyquist"
+        );
+
+        let splits = frequencies
+            .iter()
+            .map(|&f| Split::new(sample_rate, f))
+            .collect();
+        let compensation = (0..=frequencies.len())
+            .map(|band| {
+                frequencies
+                    .iter()
+                    .skip(band + 1)
+                    .map(|&f| Biquad::allpass(sample_rate, f))
+                    .collect()
+            })
+            .collect();
+
+        Crossover {
+            splits,
+            compensation,
+        }
+    }
+
+    pub fn num_bands(&self) -> usize {
+        self.splits.len() + 1
+    }
+
+    /// Splits `input` into `bands`, lowest band first. Expects `num_bands()`
+    /// output slices, each the same length as `input`.
+    pub fn process(&mut self, input: &[f32], bands: &mut [&mut [f32]]) {
+        assert_eq!(bands.len(), self.num_bands());
+        assert!(bands.iter().all(|band| band.len() == input.len()));
+
+        // The biquad states decay through denormals after the input goes silent.
+        let _denormals = DenormalGuard::new();
+        for (i, &x) in input.iter().enumerate() {
+            let mut rest = x;
+            for (band, split) in self.splits.iter_mut().enumerate() {
+                let (low, high) = split.process(rest);
+                bands[band][i] = self.compensation[band]
+                    .iter_mut()
+                    .fold(low, |y, allpass| allpass.process(y));
+                rest = high;
+            }
+            bands[self.splits.len()][i] = rest;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.splits.iter_mut().for_each(Split::reset);
+        self.compensation
+            .iter_mut()
+            .flatten()
+            .for_each(Biquad::reset);
+    }
+}
+
+/// One LR4 split point: two cascaded Butterworth sections per output.
+struct Split {
+    lowpass: [Biquad; 2],
+    highpass: [Biquad; 2],
+}
+
+impl Split {
+    fn new(sample_rate: f32, frequency: f32) -> Self {
+        Split {
+            lowpass: [
+                Biquad::lowpass(sample_rate, frequency),
+                Biquad::lowpass(sample_rate, frequency),
+            ],
+            highpass: [
+                Biquad::highpass(sample_rate, frequency),
+                Biquad::highpass(sample_rate, frequency),
+            ],
+        }
+    }
+
+    fn process(&mut self, x: f32) -> (f32, f32) {
+        let low = self
+            .lowpass
+            .iter_mut()
+            .fold(x, |y, section| section.process(y));
+        let high = self
+            .highpass
+            .iter_mut()
+            .fold(x, |y, section| section.process(y));
+        (low, high)
+    }
+
+    fn reset(&mut self) {
+        self.lowpass
+            .iter_mut()
+            .chain(self.highpass.iter_mut())
+            .for_each(Biquad::reset);
+    }
+}
+
+/// Transposed direct form II biquad with coefficients from the RBJ cookbook,
+/// all at Butterworth Q.
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn lowpass(sample_rate: f32, frequency: f32) -> Self {
+        let (cos_w0, alpha) = Self::cos_and_alpha(sample_rate, frequency);
+        let b = (1.0 - cos_w0) / 2.0;
+        Self::normalized([b, 2.0 * b, b], [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha])
+    }
+
+    fn highpass(sample_rate: f32, frequency: f32) -> Self {
+        let (cos_w0, alpha) = Self::cos_and_alpha(sample_rate, frequency);
+        let b = (1.0 + cos_w0) / 2.0;
+        Self::normalized([b, -2.0 * b, b], [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha])
+    }
+
+    /// Second-order allpass matching the phase of an LR4 lowpass plus highpass
+    /// at the same frequency.
+    fn allpass(sample_rate: f32, frequency: f32) -> Self {
+        let (cos_w0, alpha) = Self::cos_and_alpha(sample_rate, frequency);
+        Self::normalized(
+            [1.0 - alpha, -2.0 * cos_w0, 1.0 + alpha],
+            [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha],
+        )
+    }
+
+    fn cos_and_alpha(sample_rate: f32, frequency: f32) -> (f32, f32) {
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * FRAC_1_SQRT_2))
+    }
+
+    fn normalized(b: [f32; 3], a: [f32; 3]) -> Self {
+        Biquad {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+    const LEN: usize = 16384;
+
+    /// Impulse response of the sum of all bands.
+    fn summed_impulse_response(frequencies: &[f32]) -> Vec<f32> {
+        let mut crossover = Crossover::new(SAMPLE_RATE, frequencies);
+        let mut input = vec![0.0; LEN];
+        input[0] = 1.0;
+        let mut bands = vec![vec![0.0; LEN]; crossover.num_bands()];
+        let mut band_slices: Vec<&mut [f32]> = bands.iter_mut().map(Vec::as_mut_slice).collect();
+        crossover.process(&input, &mut band_slices);
+        (0..LEN)
+            .map(|i| bands.iter().map(|band| band[i]).sum())
+            .collect()
+    }
+
+    /// Magnitude in dB of the DTFT of `response` at `frequency`.
+    fn magnitude_db(response: &[f32], frequency: f32) -> f64 {
+        let w = 2.0 * std::f64::consts::PI * frequency as f64 / SAMPLE_RATE as f64;
+        let (re, im) = response
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(re, im), (n, &h)| {
+                let phase = w * n as f64;
+                (re + h as f64 * phase.cos(), im - h as f64 * phase.sin())
+            });
+        10.0 * (re * re + im * im).log10()
+    }
+
+    #[test]
+    fn summed_bands_are_flat() {
+        for frequencies in [&[1000.0][..], &[200.0, 2000.0], &[150.0, 1200.0, 6000.0]] {
+            let response = summed_impulse_response(frequencies);
+            for k in 0..40 {
+                // Log-spaced from 20 Hz to 20 kHz.
+                let frequency = 20.0 * 1000f32.powf(k as f32 / 39.0);
+                let gain = magnitude_db(&response, frequency);
+                assert!(
+                    gain.abs() < 0.01,
+                    "{frequencies:?}: {gain} dB at {frequency} Hz"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bands_are_down_6_db_at_their_crossover() {
+        let mut crossover = Crossover::new(SAMPLE_RATE, &[1000.0]);
+        let mut input = vec![0.0; LEN];
+        input[0] = 1.0;
+        let (mut low, mut high) = (vec![0.0; LEN], vec![0.0; LEN]);
+        crossover.process(&input, &mut [&mut low, &mut high]);
+        assert!((magnitude_db(&low, 1000.0) + 6.02).abs() < 0.05);
+        assert!((magnitude_db(&high, 1000.0) + 6.02).abs() < 0.05);
+    }
+}
@@ -0,0 +1,107 @@
+use std::marker::PhantomData;
+
+/// Enables flush-to-zero and denormals-are-zero on the current thread for as
+/// long as the guard lives, restoring the previous floating-point mode on drop.
+///
+/// Feedback filters whose state decays towards silence pass through
+/// denormal values, which are many times slower to compute on x86. Create a
+/// guard at the top of a processing call to treat them as zero instead.
+/// On other architectures, and on x86 without SSE, the guard does nothing.
+pub struct DenormalGuard {
+    previous: u32,
+    /// The mode belongs to the creating thread, so the guard must not move.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl DenormalGuard {
+    pub fn new() -> Self {
+        DenormalGuard {
+            previous: mode::enable_flush_to_zero(),
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Default for DenormalGuard {
+    fn default() -> Self {
+        DenormalGuard::new()
+    }
+}
+
+impl Drop for DenormalGuard {
+    fn drop(&mut self) {
+        mode::restore(self.previous);
+    }
+}
+
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse")
+))]
+mod mode {
+    use std::arch::asm;
+
+    const FLUSH_TO_ZERO: u32 = 1 << 15;
+    const DENORMALS_ARE_ZERO: u32 = 1 << 6;
+
+    /// Sets the FTZ and DAZ bits of MXCSR, returning its previous value.
+    pub fn enable_flush_to_zero() -> u32 {
+        let previous = read_mxcsr();
+        write_mxcsr(previous | FLUSH_TO_ZERO | DENORMALS_ARE_ZERO);
+        previous
+    }
+
+    pub fn restore(previous: u32) {
+        write_mxcsr(previous);
+    }
+
+    fn read_mxcsr() -> u32 {
+        let mut csr = 0u32;
+        // Safety: stmxcsr only stores the SSE control register to `csr`.
+        unsafe {
+            asm!("stmxcsr [{}]", in(reg) &mut csr, options(nostack, preserves_flags));
+        }
+        csr
+    }
+
+    fn write_mxcsr(csr: u32) {
+        // Safety: ldmxcsr only loads the SSE control register, and callers
+        // pass a previously read value with at most the FTZ/DAZ bits changed.
+        unsafe {
+            asm!("ldmxcsr [{}]", in(reg) &csr, options(nostack, preserves_flags, readonly));
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse")
+)))]
+mod mode {
+    pub fn enable_flush_to_zero() -> u32 {
+        0
+    }
+
+    pub fn restore(_previous: u32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+
+    #[test]
+    #[cfg(any(
+        target_arch = "x86_64",
+        all(target_arch = "x86", target_feature = "sse")
+    ))]
+    fn denormals_flush_to_zero_inside_guard_only() {
+        let halve_smallest_normal = || black_box(f32::MIN_POSITIVE) / black_box(2.0);
+        assert_ne!(halve_smallest_normal(), 0.0);
+        {
+            let _guard = DenormalGuard::new();
+            assert_eq!(halve_smallest_normal(), 0.0);
+        }
+        assert_ne!(halve_smallest_normal(), 0.0);
+    }
+}
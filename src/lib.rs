@@ -0,0 +1,10 @@
+pub mod command_queue;
+pub mod crossover;
+pub mod denormal;
+pub mod mixer;
+pub mod param;
+pub mod pitch;
+pub mod room_ir;
+pub mod scheduler;
+pub mod spsc;
+pub mod windows;
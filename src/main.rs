@@ -1,7 +1,7 @@
 use std::fs::File;
-use std::io::{self, BufWriter, Write};
-use std::fmt::Write as _;
-use std::io::Write as _;
+use std::io::Write;
+
+use ase::pitch::PitchDetector;
 
 fn show_info() {
     eprintln!("MUSI-6106 Assignment Executable");
@@ -9,30 +9,71 @@ fn show_info() {
 }
 
 fn main() {
-   show_info();
+    show_info();
 
     // Parse command line arguments
     // First argument is input .wav file, second argument is output text file.
+    // An optional third argument `--pitch` writes per-block pitch estimates instead of samples.
+    // It stands in for an `analyze --pitch` subcommand until the CLI grows subcommands.
     let args: Vec<String> = std::env::args().collect();
     // TODO: your code here
     let input_path: &String = &args[1];
     let output_path: &String = &args[2];
+    let pitch_mode = args.get(3).is_some_and(|arg| arg == "--pitch");
 
     // Open the input wave file and determine number of channels
     // TODO: your code here; see `hound::WavReader::open`.
     let mut reader = hound::WavReader::open(input_path).unwrap();
     let channels: u16 = reader.spec().channels;
+    let sample_rate: u32 = reader.spec().sample_rate;
 
     let samples: Vec<i16> = reader.samples().map(|s| s.unwrap()).collect();
 
     // Read audio data and write it to the output text file (one column per channel)
     // TODO: your code here; we suggest using `hound::WavReader::samples`, `File::create`, and `write!`.
     //       Remember to convert the samples to floating point values and respect the number of channels!
-    let output_file = File::create(output_path);
+    let mut output_file = File::create(output_path).unwrap();
     // let mut writer = BufWriter::new(output_file);
 
-    for i in 0..samples.len() {
-        // dbg!(samples[i]);
-        writeln!(output_file, "{}", samples[i]);
+    if pitch_mode {
+        write_pitch(&samples, channels, sample_rate, &mut output_file);
+        return;
+    }
+
+    for sample in &samples {
+        writeln!(output_file, "{}", sample).unwrap();
+    }
+}
+
+/// Writes one line per analysis block: start time in seconds, f0 in Hz (0 when
+/// unvoiced) and confidence, tab-separated.
+fn write_pitch(samples: &[i16], channels: u16, sample_rate: u32, output: &mut impl Write) {
+    const MIN_F0: f32 = 60.0;
+    const MAX_F0: f32 = 1000.0;
+
+    // Downmix to mono floating point before analysis.
+    let mono: Vec<f32> = samples
+        .chunks_exact(channels as usize)
+        .map(|frame| frame.iter().map(|&s| s as f32 / 32768.0).sum::<f32>() / channels as f32)
+        .collect();
+
+    // The block must hold at least two periods of the lowest f0.
+    let block_size = (2 * (sample_rate as f32 / MIN_F0).ceil() as usize + 1).next_power_of_two();
+    let mut detector = PitchDetector::new(sample_rate as f32, block_size, MIN_F0, MAX_F0, 0.15);
+
+    // The last partial block is zero-padded, so input shorter than one block still
+    // gets an estimate.
+    let mut padded = vec![0.0; block_size];
+    for (i, block) in mono.chunks(block_size).enumerate() {
+        let estimate = if block.len() == block_size {
+            detector.process(block)
+        } else {
+            padded[..block.len()].copy_from_slice(block);
+            padded[block.len()..].fill(0.0);
+            detector.process(&padded)
+        };
+        let time = (i * block_size) as f32 / sample_rate as f32;
+        let f0 = estimate.f0.unwrap_or(0.0);
+        writeln!(output, "{}\t{}\t{}", time, f0, estimate.confidence).unwrap();
     }
 }
@@ -0,0 +1,131 @@
+use std::f32::consts::FRAC_PI_4;
+
+/// Sums N mono input buses into a stereo output, each with its own gain,
+/// pan, mute and solo.
+///
+/// Panning uses a constant-power law, so a centred bus appears at -3 dB in
+/// each channel. When any bus is soloed, only soloed buses are heard; mute
+/// takes precedence over solo.
+pub struct Mixer {
+    buses: Vec<Bus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bus {
+    gain: f32,
+    pan: f32,
+    mute: bool,
+    solo: bool,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Bus {
+            gain: 1.0,
+            pan: 0.0,
+            mute: false,
+            solo: false,
+        }
+    }
+}
+
+impl Mixer {
+    /// Creates a mixer with `num_buses` buses at unity gain, panned centre.
+    pub fn new(num_buses: usize) -> Self {
+        assert!(num_buses > 0, "mixer needs at least one bus");
+        Mixer {
+            buses: vec![Bus::default(); num_buses],
+        }
+    }
+
+    pub fn num_buses(&self) -> usize {
+        self.buses.len()
+    }
+
+    /// Sets the linear gain of `bus`.
+    pub fn set_gain(&mut self, bus: usize, gain: f32) {
+        assert!(gain >= 0.0, "bus gain must be non-negative");
+        self.buses[bus].gain = gain;
+    }
+
+    /// Sets the pan of `bus`, from -1 (hard left) to 1 (hard right).
+    pub fn set_pan(&mut self, bus: usize, pan: f32) {
+        assert!((-1.0..=1.0).contains(&pan), "pan must lie in [-1, 1]");
+        self.buses[bus].pan = pan;
+    }
+
+    pub fn set_mute(&mut self, bus: usize, mute: bool) {
+        self.buses[bus].mute = mute;
+    }
+
+    pub fn set_solo(&mut self, bus: usize, solo: bool) {
+        self.buses[bus].solo = solo;
+    }
+
+    /// Mixes one block. Expects `num_buses()` inputs, each the same length as
+    /// `left` and `right`, which are overwritten with the stereo sum.
+    pub fn process(&self, inputs: &[&[f32]], left: &mut [f32], right: &mut [f32]) {
+        assert_eq!(inputs.len(), self.num_buses());
+        assert_eq!(left.len(), right.len());
+        assert!(inputs.iter().all(|input| input.len() == left.len()));
+
+        left.fill(0.0);
+        right.fill(0.0);
+
+        let any_solo = self.buses.iter().any(|bus| bus.solo);
+        for (bus, input) in self.buses.iter().zip(inputs) {
+            if bus.mute || (any_solo && !bus.solo) {
+                continue;
+            }
+            let angle = (bus.pan + 1.0) * FRAC_PI_4;
+            let (gain_left, gain_right) = (bus.gain * angle.cos(), bus.gain * angle.sin());
+            for ((&x, l), r) in input.iter().zip(left.iter_mut()).zip(right.iter_mut()) {
+                *l += gain_left * x;
+                *r += gain_right * x;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    fn mix(mixer: &Mixer, inputs: &[&[f32]]) -> (Vec<f32>, Vec<f32>) {
+        let mut left = vec![0.0; inputs[0].len()];
+        let mut right = vec![0.0; inputs[0].len()];
+        mixer.process(inputs, &mut left, &mut right);
+        (left, right)
+    }
+
+    #[test]
+    fn centred_buses_sum_at_constant_power() {
+        let mixer = Mixer::new(2);
+        let (left, right) = mix(&mixer, &[&[1.0, 0.5], &[1.0, -0.5]]);
+        assert!((left[0] - 2.0 * FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!((right[0] - 2.0 * FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!(left[1].abs() < 1e-6 && right[1].abs() < 1e-6);
+    }
+
+    #[test]
+    fn gain_and_hard_pan() {
+        let mut mixer = Mixer::new(2);
+        mixer.set_gain(0, 0.5);
+        mixer.set_pan(0, -1.0);
+        mixer.set_pan(1, 1.0);
+        let (left, right) = mix(&mixer, &[&[1.0], &[1.0]]);
+        assert!((left[0] - 0.5).abs() < 1e-6);
+        assert!((right[0] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn solo_excludes_others_and_mute_wins() {
+        let mut mixer = Mixer::new(3);
+        mixer.set_solo(1, true);
+        mixer.set_solo(2, true);
+        mixer.set_mute(2, true);
+        let (left, _) = mix(&mixer, &[&[1.0], &[2.0], &[4.0]]);
+        assert!((left[0] - 2.0 * FRAC_1_SQRT_2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Creates a linked pair for one `f32` parameter, starting at `initial`.
+///
+/// The handle is cloneable and can be shared with UI or control threads;
+/// the receiver stays with the audio thread. Both sides only do a single
+/// atomic load or store, so reads on the audio thread are wait-free.
+pub fn param(initial: f32) -> (ParamHandle, ParamReceiver) {
+    let value = Arc::new(AtomicU32::new(initial.to_bits()));
+    (
+        ParamHandle {
+            value: Arc::clone(&value),
+        },
+        ParamReceiver {
+            value,
+            last_seen: initial.to_bits(),
+        },
+    )
+}
+
+/// Control-thread side of a parameter.
+#[derive(Clone)]
+pub struct ParamHandle {
+    value: Arc<AtomicU32>,
+}
+
+/// Audio-thread side of a parameter.
+pub struct ParamReceiver {
+    value: Arc<AtomicU32>,
+    last_seen: u32,
+}
+
+impl ParamHandle {
+    pub fn set(&self, value: f32) {
+        self.value.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.value.load(Ordering::Relaxed))
+    }
+}
+
+impl ParamReceiver {
+    /// Latest value set through any handle.
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.value.load(Ordering::Relaxed))
+    }
+
+    /// Returns the latest value if it differs from the one returned by the
+    /// previous `poll` (or the initial value), so processors only recompute
+    /// derived coefficients when something changed.
+    pub fn poll(&mut self) -> Option<f32> {
+        let bits = self.value.load(Ordering::Relaxed);
+        if bits == self.last_seen {
+            None
+        } else {
+            self.last_seen = bits;
+            Some(f32::from_bits(bits))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn poll_reports_only_changes() {
+        let (handle, mut receiver) = param(0.5);
+        assert_eq!(receiver.get(), 0.5);
+        assert_eq!(receiver.poll(), None);
+        handle.set(0.25);
+        assert_eq!(receiver.poll(), Some(0.25));
+        assert_eq!(receiver.poll(), None);
+        handle.set(0.25);
+        assert_eq!(receiver.poll(), None);
+    }
+
+    #[test]
+    fn updates_cross_threads() {
+        let (handle, receiver) = param(0.0);
+        let control = handle.clone();
+        thread::spawn(move || control.set(-3.0)).join().unwrap();
+        assert_eq!(receiver.get(), -3.0);
+        assert_eq!(handle.get(), -3.0);
+    }
+}
@@ -0,0 +1,187 @@
+/// Fundamental frequency estimate for one analysis block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchEstimate {
+    /// Estimated f0 in Hz, or `None` if the block is unvoiced.
+    pub f0: Option<f32>,
+    /// 1 minus the normalized YIN difference at the chosen lag, in [0, 1].
+    pub confidence: f32,
+}
+
+/// Monophonic pitch tracker based on the YIN algorithm
+/// (de Cheveigné & Kawahara, 2002).
+///
+/// Each call to `process` analyzes one block of `block_size` samples and
+/// searches lags corresponding to the range `[min_f0, max_f0]`.
+pub struct PitchDetector {
+    sample_rate: f32,
+    block_size: usize,
+    threshold: f32,
+    min_lag: usize,
+    max_lag: usize,
+    difference: Vec<f32>,
+}
+
+impl PitchDetector {
+    /// Creates a detector for blocks of `block_size` samples.
+    ///
+    /// `threshold` is the YIN absolute threshold on the cumulative mean
+    /// normalized difference; 0.1 to 0.2 is typical.
+    pub fn new(
+        sample_rate: f32,
+        block_size: usize,
+        min_f0: f32,
+        max_f0: f32,
+        threshold: f32,
+    ) -> Self {
+        assert!(sample_rate > 0.0, "sample rate must be positive");
+        assert!(
+            0.0 < min_f0 && min_f0 < max_f0,
+            "f0 range must satisfy 0 < min_f0 < max_f0"
+        );
+        assert!(
+            threshold > 0.0 && threshold < 1.0,
+            "threshold must lie strictly between 0 and 1"
+        );
+
+        let min_lag = ((sample_rate / max_f0).floor() as usize).max(2);
+        let max_lag = (sample_rate / min_f0).ceil() as usize;
+        assert!(
+            block_size > 2 * max_lag,
+            "block size must be more than twice the longest period (sample_rate / min_f0)"
+        );
+
+        PitchDetector {
+            sample_rate,
+            block_size,
+            threshold,
+            min_lag,
+            max_lag,
+            difference: vec![0.0; max_lag + 2],
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Estimates the pitch of one block. `block` must hold exactly `block_size` samples.
+    pub fn process(&mut self, block: &[f32]) -> PitchEstimate {
+        assert_eq!(block.len(), self.block_size);
+
+        // Difference function over a window that fits inside the block at every lag.
+        let window = self.block_size - self.max_lag - 1;
+        for lag in 1..self.difference.len() {
+            self.difference[lag] = (0..window)
+                .map(|i| {
+                    let d = block[i] - block[i + lag];
+                    d * d
+                })
+                .sum();
+        }
+
+        // Cumulative mean normalized difference; d'(0) is defined as 1.
+        let mut running_sum = 0.0;
+        self.difference[0] = 1.0;
+        for lag in 1..self.difference.len() {
+            running_sum += self.difference[lag];
+            self.difference[lag] = if running_sum > 0.0 {
+                self.difference[lag] * lag as f32 / running_sum
+            } else {
+                1.0
+            };
+        }
+
+        // First dip below the threshold, followed down to its local minimum.
+        let mut best = None;
+        let mut lag = self.min_lag;
+        while lag <= self.max_lag {
+            if self.difference[lag] < self.threshold {
+                while lag < self.max_lag && self.difference[lag + 1] < self.difference[lag] {
+                    lag += 1;
+                }
+                best = Some(lag);
+                break;
+            }
+            lag += 1;
+        }
+
+        match best {
+            Some(lag) => {
+                let period = self.parabolic_peak(lag);
+                PitchEstimate {
+                    f0: Some(self.sample_rate / period),
+                    confidence: (1.0 - self.difference[lag]).clamp(0.0, 1.0),
+                }
+            }
+            None => {
+                let lowest = self.difference[self.min_lag..=self.max_lag]
+                    .iter()
+                    .fold(f32::INFINITY, |acc, &d| acc.min(d));
+                PitchEstimate {
+                    f0: None,
+                    confidence: (1.0 - lowest).clamp(0.0, 1.0),
+                }
+            }
+        }
+    }
+
+    /// Refines an integer lag to a fractional period by fitting a parabola
+    /// through the neighbouring difference values.
+    fn parabolic_peak(&self, lag: usize) -> f32 {
+        let (prev, cur, next) = (
+            self.difference[lag - 1],
+            self.difference[lag],
+            self.difference[lag + 1],
+        );
+        let denominator = prev - 2.0 * cur + next;
+        if denominator.abs() < f32::EPSILON {
+            lag as f32
+        } else {
+            lag as f32 + 0.5 * (prev - next) / denominator
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+    const BLOCK_SIZE: usize = 2048;
+
+    fn detector() -> PitchDetector {
+        PitchDetector::new(SAMPLE_RATE, BLOCK_SIZE, 60.0, 1000.0, 0.15)
+    }
+
+    #[test]
+    fn sine_f0_is_detected() {
+        let mut detector = detector();
+        for f0 in [82.41, 220.0, 440.0, 987.77] {
+            let block: Vec<f32> = (0..BLOCK_SIZE)
+                .map(|i| (2.0 * PI * f0 * i as f32 / SAMPLE_RATE).sin())
+                .collect();
+            let estimate = detector.process(&block);
+            let detected = estimate.f0.expect("sine should be voiced");
+            assert!(
+                (detected - f0).abs() < f0 * 0.005,
+                "{f0} Hz detected as {detected} Hz"
+            );
+            assert!(estimate.confidence > 0.9);
+        }
+    }
+
+    #[test]
+    fn silence_is_unvoiced() {
+        let mut detector = detector();
+        let estimate = detector.process(&[0.0; BLOCK_SIZE]);
+        assert_eq!(estimate.f0, None);
+        assert_eq!(estimate.confidence, 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn threshold_out_of_range_panics() {
+        PitchDetector::new(SAMPLE_RATE, BLOCK_SIZE, 60.0, 1000.0, 1.5);
+    }
+}
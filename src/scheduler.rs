@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// One step of a block run by `EventScheduler::run_block`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step<E> {
+    /// An event due at the current position in the block; apply it before
+    /// rendering further.
+    Event(E),
+    /// A range of sample offsets within the block to render with the current state.
+    Render(Range<usize>),
+}
+
+/// Queue of events timestamped in samples that splits each processing block
+/// at event boundaries, so parameter changes, note on/off and preset
+/// switches land on exact samples.
+///
+/// Time starts at 0 and advances by the length of each block passed to
+/// `run_block`.
+pub struct EventScheduler<E> {
+    /// Pending events sorted by time; events with equal times keep their
+    /// scheduling order.
+    queue: VecDeque<(u64, E)>,
+    now: u64,
+}
+
+impl<E> Default for EventScheduler<E> {
+    fn default() -> Self {
+        EventScheduler::new()
+    }
+}
+
+impl<E> EventScheduler<E> {
+    pub fn new() -> Self {
+        EventScheduler::with_capacity(0)
+    }
+
+    /// Creates a scheduler with room for `capacity` pending events before it
+    /// needs to reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        EventScheduler {
+            queue: VecDeque::with_capacity(capacity),
+            now: 0,
+        }
+    }
+
+    /// Sample time at which the next block starts.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Number of pending events.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Queues `event` for sample time `time`. Events scheduled in the past
+    /// fire at the start of the next block.
+    pub fn schedule(&mut self, time: u64, event: E) {
+        let index = self.queue.partition_point(|&(queued, _)| queued <= time);
+        self.queue.insert(index, (time, event));
+    }
+
+    /// Drops all pending events without firing them.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Runs one block of `len` samples, calling `step` with the events due in
+    /// the block and the ranges to render between them, in time order.
+    ///
+    /// The `Render` ranges cover `0..len` exactly. An event at offset `n` is
+    /// delivered before the range starting at `n`.
+    pub fn run_block(&mut self, len: usize, mut step: impl FnMut(Step<E>)) {
+        let end = self.now + len as u64;
+        let mut position = 0;
+        while let Some(&(time, _)) = self.queue.front() {
+            if time >= end {
+                break;
+            }
+            let offset = time.saturating_sub(self.now) as usize;
+            if offset > position {
+                step(Step::Render(position..offset));
+                position = offset;
+            }
+            if let Some((_, event)) = self.queue.pop_front() {
+                step(Step::Event(event));
+            }
+        }
+        if position < len {
+            step(Step::Render(position..len));
+        }
+        self.now = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(scheduler: &mut EventScheduler<&'static str>, len: usize) -> Vec<Step<&'static str>> {
+        let mut steps = Vec::new();
+        scheduler.run_block(len, |step| steps.push(step));
+        steps
+    }
+
+    #[test]
+    fn blocks_split_at_event_offsets() {
+        let mut scheduler = EventScheduler::new();
+        scheduler.schedule(70, "b");
+        scheduler.schedule(10, "a");
+        scheduler.schedule(64, "next block");
+
+        assert_eq!(
+            run(&mut scheduler, 64),
+            [Step::Render(0..10), Step::Event("a"), Step::Render(10..64),]
+        );
+        assert_eq!(
+            run(&mut scheduler, 64),
+            [
+                Step::Event("next block"),
+                Step::Render(0..6),
+                Step::Event("b"),
+                Step::Render(6..64),
+            ]
+        );
+        assert!(scheduler.is_empty());
+        assert_eq!(scheduler.now(), 128);
+    }
+
+    #[test]
+    fn equal_times_keep_order_and_past_events_fire_first() {
+        let mut scheduler = EventScheduler::new();
+        run(&mut scheduler, 32);
+        scheduler.schedule(40, "first");
+        scheduler.schedule(40, "second");
+        scheduler.schedule(5, "late");
+
+        assert_eq!(
+            run(&mut scheduler, 32),
+            [
+                Step::Event("late"),
+                Step::Render(0..8),
+                Step::Event("first"),
+                Step::Event("second"),
+                Step::Render(8..32),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_queue_renders_whole_block() {
+        let mut scheduler = EventScheduler::<&str>::new();
+        assert_eq!(run(&mut scheduler, 16), [Step::Render(0..16)]);
+        assert_eq!(run(&mut scheduler, 0), []);
+    }
+}
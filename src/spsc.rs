@@ -0,0 +1,264 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Creates a bounded single-producer/single-consumer ring buffer holding up
+/// to `capacity` items, split into its two ends.
+///
+/// `push` and `pop` are wait-free: each is a handful of atomic loads and
+/// stores with no locks or allocation, so either end can live on a real-time
+/// audio thread while the other runs on a file-reader or GUI thread.
+pub fn channel<T: Send>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+    assert!(capacity <= usize::MAX / 2, "capacity is too large");
+    let shared = Arc::new(Shared {
+        slots: (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect(),
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+    (
+        Producer {
+            shared: Arc::clone(&shared),
+        },
+        Consumer { shared },
+    )
+}
+
+/// Writing end of an SPSC ring buffer.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Reading end of an SPSC ring buffer.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// `head` and `tail` count pushes and pops modulo `2 * capacity`, which
+/// keeps "full" (`capacity` apart) distinct from "empty" (equal) while every
+/// index maps to the same slot on each lap.
+struct Shared<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    /// Next write index; only the producer stores to it.
+    head: AtomicUsize,
+    /// Next read index; only the consumer stores to it.
+    tail: AtomicUsize,
+}
+
+// Safety: a slot is only accessed by the producer while it is not between
+// `tail` and `head` and only by the consumer while it is, and the
+// release/acquire pairs on `head` and `tail` hand each slot over between them.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        self.distance(tail, head)
+    }
+
+    /// Number of steps from index `from` forward to index `to`.
+    fn distance(&self, from: usize, to: usize) -> usize {
+        if to >= from {
+            to - from
+        } else {
+            to + 2 * self.capacity() - from
+        }
+    }
+
+    fn slot(&self, index: usize) -> &UnsafeCell<MaybeUninit<T>> {
+        let capacity = self.capacity();
+        &self.slots[if index >= capacity {
+            index - capacity
+        } else {
+            index
+        }]
+    }
+
+    fn next(&self, index: usize) -> usize {
+        if index + 1 == 2 * self.capacity() {
+            0
+        } else {
+            index + 1
+        }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let mut tail = *self.tail.get_mut();
+        while tail != head {
+            // Safety: slots from `tail` up to `head` were written and never read.
+            unsafe { (*self.slot(tail).get()).assume_init_drop() };
+            tail = self.next(tail);
+        }
+    }
+}
+
+impl<T> Producer<T> {
+    /// Appends `value`, or hands it back if the buffer is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Relaxed);
+        let tail = shared.tail.load(Ordering::Acquire);
+        if shared.distance(tail, head) == shared.capacity() {
+            return Err(value);
+        }
+        // Safety: the slot is not between `tail` and `head`, so the consumer
+        // will not touch it until `head` is published below.
+        unsafe { (*shared.slot(head).get()).write(value) };
+        shared.head.store(shared.next(head), Ordering::Release);
+        Ok(())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Number of queued items; may be stale by the time it is used.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Removes the oldest item, or returns `None` if the buffer is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Relaxed);
+        let head = shared.head.load(Ordering::Acquire);
+        if tail == head {
+            return None;
+        }
+        // Safety: the slot is between `tail` and `head`, so the producer
+        // finished writing it and will not reuse it until `tail` is published below.
+        let value = unsafe { (*shared.slot(tail).get()).assume_init_read() };
+        shared.tail.store(shared.next(tail), Ordering::Release);
+        Some(value)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Number of queued items; may be stale by the time it is used.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// Creates a channel whose indices start at `start` instead of 0.
+    fn with_start_index<T: Send>(capacity: usize, start: usize) -> (Producer<T>, Consumer<T>) {
+        assert!(start < 2 * capacity);
+        let (producer, consumer) = channel(capacity);
+        producer.shared.head.store(start, Ordering::Relaxed);
+        producer.shared.tail.store(start, Ordering::Relaxed);
+        (producer, consumer)
+    }
+
+    #[test]
+    fn fifo_order_and_bounds() {
+        let (mut producer, mut consumer) = channel(3);
+        assert!(consumer.is_empty());
+        assert_eq!(consumer.pop(), None);
+        for i in 0..3 {
+            producer.push(i).unwrap();
+        }
+        assert!(producer.is_full());
+        assert_eq!(producer.push(3), Err(3));
+        assert_eq!(consumer.pop(), Some(0));
+        producer.push(3).unwrap();
+        assert_eq!(consumer.len(), 3);
+        assert_eq!(
+            (0..3).map(|_| consumer.pop().unwrap()).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(consumer.pop(), None);
+    }
+
+    #[test]
+    fn cross_thread_delivery_is_ordered() {
+        const COUNT: usize = 100_000;
+        let (mut producer, mut consumer) = channel::<usize>(64);
+        let writer = thread::spawn(move || {
+            for i in 0..COUNT {
+                let mut value = i;
+                while let Err(rejected) = producer.push(value) {
+                    value = rejected;
+                    thread::yield_now();
+                }
+            }
+        });
+        let mut expected = 0;
+        while expected < COUNT {
+            match consumer.pop() {
+                Some(value) => {
+                    assert_eq!(value, expected);
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn indices_wrap_without_reusing_live_slots() {
+        // Capacity 3 does not divide the index range evenly, and starting
+        // one step before the wrap exercises it immediately.
+        let (mut producer, mut consumer) = with_start_index::<String>(3, 5);
+        let mut next_push = 0;
+        let mut next_pop = 0;
+        for _ in 0..20 {
+            while producer.push(next_push.to_string()).is_ok() {
+                next_push += 1;
+            }
+            assert!(producer.is_full());
+            assert_eq!(consumer.pop(), Some(next_pop.to_string()));
+            assert_eq!(consumer.pop(), Some((next_pop + 1).to_string()));
+            next_pop += 2;
+            assert_eq!(consumer.len(), 1);
+        }
+    }
+
+    #[test]
+    fn unread_items_are_dropped() {
+        let item = Arc::new(());
+        let (mut producer, consumer) = channel(4);
+        producer.push(Arc::clone(&item)).unwrap();
+        producer.push(Arc::clone(&item)).unwrap();
+        assert_eq!(Arc::strong_count(&item), 3);
+        drop(producer);
+        drop(consumer);
+        assert_eq!(Arc::strong_count(&item), 1);
+    }
+}
@@ -0,0 +1,187 @@
+use std::f64::consts::PI;
+
+/// Window shapes available to spectral analysis and resampling code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowType {
+    Hann,
+    Hamming,
+    /// 4-term Blackman-Harris (-92 dB sidelobes).
+    BlackmanHarris,
+    /// Kaiser window with shape parameter `beta`; larger values trade
+    /// main-lobe width for sidelobe attenuation.
+    Kaiser {
+        beta: f32,
+    },
+}
+
+/// Whether the window is sampled for filter design or for spectral analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Endpoints are equal; use for FIR design (e.g. windowed sinc).
+    Symmetric,
+    /// One period of an `N`-periodic window; use for STFT frames so that
+    /// overlap-add sums are exact.
+    Periodic,
+}
+
+/// Returns a window of `len` samples.
+pub fn generate(window: WindowType, len: usize, symmetry: Symmetry) -> Vec<f32> {
+    let mut output = vec![0.0; len];
+    fill(window, symmetry, &mut output);
+    output
+}
+
+/// Writes a window of `output.len()` samples into `output` without allocating.
+pub fn fill(window: WindowType, symmetry: Symmetry, output: &mut [f32]) {
+    let len = output.len();
+    if len <= 1 {
+        output.fill(1.0);
+        return;
+    }
+    let period = match symmetry {
+        Symmetry::Symmetric => (len - 1) as f64,
+        Symmetry::Periodic => len as f64,
+    };
+
+    match window {
+        WindowType::Hann => cosine_sum(&[0.5, 0.5], period, output),
+        WindowType::Hamming => cosine_sum(&[0.54, 0.46], period, output),
+        WindowType::BlackmanHarris => {
+            cosine_sum(&[0.35875, 0.48829, 0.14128, 0.01168], period, output)
+        }
+        WindowType::Kaiser { beta } => {
+            let beta = beta as f64;
+            let denominator = bessel_i0(beta);
+            for (n, sample) in output.iter_mut().enumerate() {
+                let x = 2.0 * n as f64 / period - 1.0;
+                *sample = (bessel_i0(beta * (1.0 - x * x).max(0.0).sqrt()) / denominator) as f32;
+            }
+        }
+    }
+}
+
+/// Scales the window so its largest sample is 1.
+pub fn normalize_peak(window: &mut [f32]) {
+    let peak = window.iter().fold(0.0f32, |acc, &w| acc.max(w.abs()));
+    if peak > 0.0 {
+        window.iter_mut().for_each(|w| *w /= peak);
+    }
+}
+
+/// Scales the window so its samples sum to 1 (unity coherent gain), so a
+/// windowed sinusoid of amplitude `a` shows up as `a / 2` in the spectrum.
+pub fn normalize_sum(window: &mut [f32]) {
+    let sum: f32 = window.iter().sum();
+    if sum != 0.0 {
+        window.iter_mut().for_each(|w| *w /= sum);
+    }
+}
+
+/// Scales the window to unit RMS, preserving the power of windowed noise.
+pub fn normalize_energy(window: &mut [f32]) {
+    if window.is_empty() {
+        return;
+    }
+    let rms = (window.iter().map(|w| w * w).sum::<f32>() / window.len() as f32).sqrt();
+    if rms > 0.0 {
+        window.iter_mut().for_each(|w| *w /= rms);
+    }
+}
+
+/// Sums copies of `window` shifted by multiples of `hop` and returns one
+/// hop's worth of the result.
+///
+/// For a window satisfying the constant-overlap-add (COLA) condition at this
+/// hop, every value is the same.
+pub fn overlap_add_sum(window: &[f32], hop: usize) -> Vec<f32> {
+    assert!(hop > 0, "hop size must be positive");
+    let mut sum = vec![0.0; hop];
+    for (n, &w) in window.iter().enumerate() {
+        sum[n % hop] += w;
+    }
+    sum
+}
+
+/// Returns the overlap-add gain of `window` at `hop` if it is constant to
+/// within `tolerance` (relative), or `None` if the COLA condition fails.
+///
+/// Dividing the overlap-added output by this gain restores unity level.
+pub fn cola_gain(window: &[f32], hop: usize, tolerance: f32) -> Option<f32> {
+    let sum = overlap_add_sum(window, hop);
+    let (min, max) = sum
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &s| {
+            (lo.min(s), hi.max(s))
+        });
+    let mean = sum.iter().sum::<f32>() / hop as f32;
+    if mean > 0.0 && (max - min) <= tolerance * mean {
+        Some(mean)
+    } else {
+        None
+    }
+}
+
+fn cosine_sum(coefficients: &[f64], period: f64, output: &mut [f32]) {
+    for (n, sample) in output.iter_mut().enumerate() {
+        let phase = 2.0 * PI * n as f64 / period;
+        *sample = coefficients
+            .iter()
+            .enumerate()
+            .map(|(k, &a)| {
+                let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                sign * a * (k as f64 * phase).cos()
+            })
+            .sum::<f64>() as f32;
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind, by power series.
+fn bessel_i0(x: f64) -> f64 {
+    let half_x = x / 2.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-12 {
+        term *= (half_x / k) * (half_x / k);
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periodic_hann_is_cola_at_half_overlap() {
+        let window = generate(WindowType::Hann, 1024, Symmetry::Periodic);
+        let gain = cola_gain(&window, 512, 1e-5).expect("periodic Hann should be COLA");
+        assert!((gain - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn symmetric_windows_have_equal_endpoints() {
+        for window in [
+            WindowType::Hann,
+            WindowType::Hamming,
+            WindowType::BlackmanHarris,
+            WindowType::Kaiser { beta: 8.6 },
+        ] {
+            let w = generate(window, 33, Symmetry::Symmetric);
+            for n in 0..w.len() / 2 {
+                assert!(
+                    (w[n] - w[w.len() - 1 - n]).abs() < 1e-6,
+                    "{window:?} is not symmetric"
+                );
+            }
+            assert!((w[16] - 1.0).abs() < 1e-6, "{window:?} does not peak at 1");
+        }
+    }
+
+    #[test]
+    fn kaiser_beta_zero_is_rectangular() {
+        let w = generate(WindowType::Kaiser { beta: 0.0 }, 64, Symmetry::Symmetric);
+        assert!(w.iter().all(|&x| (x - 1.0).abs() < 1e-6));
+    }
+}